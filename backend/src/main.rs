@@ -3,32 +3,56 @@
 //! Reads telem.txt from the CubeSat simulator and serves it as a JSON API.
 //! Compatible with the SRG Dashboard frontend.
 
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
 use axum::{
-    extract::State,
-    http::header,
+    extract::{FromRequestParts, Query, State},
+    http::{header, request::Parts, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
     routing::{get, post},
-    Json, Router,
+    Json, Router, RequestPartsExt,
 };
+use axum_extra::headers::authorization::Bearer;
+use axum_extra::headers::Authorization;
+use axum_extra::TypedHeader;
+use futures_util::Stream;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header as JwtHeader, Validation};
+use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo};
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::convert::Infallible;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use sysinfo::System;
+use sysinfo::{Pid, System};
 use tokio::fs;
-use tokio::sync::RwLock;
-use tower_http::cors::{Any, CorsLayer};
+use tokio::sync::{broadcast, RwLock};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt as _;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::services::ServeDir;
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
 
 const DEFAULT_PORT: u16 = 5050;
 const STALE_TIMEOUT_SECS: u64 = 120;
-#[derive(Clone, Default, Serialize, Deserialize)]
+const JWT_EXPIRY_SECS: u64 = 12 * 60 * 60;
+const TELEMETRY_BROADCAST_CAPACITY: usize = 32;
+const SSE_KEEPALIVE_SECS: u64 = 15;
+const HISTORY_RING_CAPACITY: usize = 10_000;
+const DEFAULT_HISTORY_LIMIT: usize = 500;
+#[derive(Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
 struct Ms5611 {
     temp: f64,
     pressure: f64,
     altitude: f64,
 }
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
 struct Mpu6050 {
     gx: f64,
     gy: f64,
@@ -38,13 +62,13 @@ struct Mpu6050 {
     az: f64,
 }
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
 struct SystemInfo {
     cpu: f64,
     gpu_temp: f64,
 }
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
 struct Telemetry {
     status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -55,46 +79,255 @@ struct Telemetry {
     system: SystemInfo,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
 struct User {
     id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pw: Option<String>,
+    /// Argon2id PHC string, e.g. `$argon2id$v=19$...`. Legacy `users.json`
+    /// files may still hold the old cleartext password here; `post_auth`
+    /// detects that case and re-hashes it on first successful login.
+    pw: String,
     role: String,
     created: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    role: String,
+    exp: usize,
+}
+
 #[derive(Clone)]
 struct AppState {
     telemetry: Arc<RwLock<Telemetry>>,
+    telemetry_tx: broadcast::Sender<Telemetry>,
+    history: Arc<RwLock<VecDeque<Telemetry>>>,
+    history_db: Arc<Mutex<Connection>>,
     users: Arc<RwLock<Vec<User>>>,
     users_file: PathBuf,
     telem_file: PathBuf,
+    poll_interval_secs: u64,
+    stale_timeout_secs: u64,
+    jwt_secret: String,
+    /// Listen port, so `/api/clients` can filter connections down to this server's socket.
+    port: u16,
+}
+
+/// `[server]` section of `config.toml`.
+#[derive(Deserialize)]
+#[serde(default)]
+struct ServerConfig {
+    bind_addr: String,
+    port: u16,
+    /// Directory the dashboard's static assets are served from. Defaults to the
+    /// backend's parent directory, matching the layout of this repo.
+    dashboard_root: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: "0.0.0.0".into(),
+            port: DEFAULT_PORT,
+            dashboard_root: None,
+        }
+    }
+}
+
+/// `[telemetry]` section of `config.toml`.
+#[derive(Deserialize)]
+#[serde(default)]
+struct TelemetryConfig {
+    file: String,
+    poll_interval_secs: u64,
+    stale_timeout_secs: u64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            file: "telem.txt".into(),
+            poll_interval_secs: 1,
+            stale_timeout_secs: STALE_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// `[security]` section of `config.toml`.
+#[derive(Deserialize)]
+#[serde(default)]
+struct SecurityConfig {
+    /// Empty means "allow any origin", matching the previous hardcoded CORS policy.
+    cors_allowed_origins: Vec<String>,
+    jwt_secret: String,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            cors_allowed_origins: Vec::new(),
+            jwt_secret: "cubesat-sim-dev-secret-change-me".into(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct Config {
+    #[serde(default)]
+    server: ServerConfig,
+    #[serde(default)]
+    telemetry: TelemetryConfig,
+    #[serde(default)]
+    security: SecurityConfig,
+}
+
+/// Loads `config.toml` if present, falling back to defaults for anything missing.
+/// `PORT`, `TELEM_FILE`, and `JWT_SECRET` env vars still override the file, so existing
+/// deployments that only set env vars keep working unchanged.
+fn load_config(path: &PathBuf) -> Config {
+    let mut config = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| toml::from_str::<Config>(&raw).ok())
+        .unwrap_or_default();
+
+    if let Some(port) = std::env::var("PORT").ok().and_then(|v| v.parse().ok()) {
+        config.server.port = port;
+    }
+    if let Ok(file) = std::env::var("TELEM_FILE") {
+        config.telemetry.file = file;
+    }
+    if let Ok(secret) = std::env::var("JWT_SECRET") {
+        config.security.jwt_secret = secret;
+    }
+
+    config
 }
 
 fn default_users() -> Vec<User> {
     vec![
         User {
             id: "flyboysam".into(),
-            pw: Some("Airplane11!".into()),
+            pw: hash_password("Airplane11!"),
             role: "admin".into(),
             created: "SYSTEM".into(),
         },
         User {
             id: "guest".into(),
-            pw: Some("guest123".into()),
+            pw: hash_password("guest123"),
             role: "guest".into(),
             created: "2026-02-22".into(),
         },
         User {
             id: "SRG".into(),
-            pw: Some("SRG_2026".into()),
+            pw: hash_password("SRG_2026"),
             role: "guest".into(),
             created: "2026-02-22".into(),
         },
     ]
 }
 
+/// Hashes a password into an Argon2id PHC string suitable for storage in `users.json`.
+fn hash_password(pw: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(pw.as_bytes(), &salt)
+        .expect("argon2 hashing should not fail")
+        .to_string()
+}
+
+/// Returns `true` if `stored` looks like an Argon2 PHC string rather than a legacy cleartext password.
+fn is_phc_hash(stored: &str) -> bool {
+    stored.starts_with("$argon2")
+}
+
+fn verify_password(pw: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default().verify_password(pw.as_bytes(), &parsed).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// A fixed Argon2 hash with no corresponding real account, used to give unknown
+/// usernames the same verification cost as a real one so login timing doesn't
+/// leak which usernames exist.
+fn dummy_password_hash() -> &'static str {
+    static DUMMY: OnceLock<String> = OnceLock::new();
+    DUMMY.get_or_init(|| hash_password("correct-horse-battery-staple-dummy"))
+}
+
+fn issue_token(secret: &str, id: &str, role: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let claims = Claims {
+        sub: id.to_string(),
+        role: role.to_string(),
+        exp: (now + JWT_EXPIRY_SECS) as usize,
+    };
+    encode(
+        &JwtHeader::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+fn decode_token(secret: &str, token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map(|data| data.claims)
+}
+
+fn unauthorized(msg: &str) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(serde_json::json!({ "ok": false, "error": msg })),
+    )
+}
+
+/// Decoded identity attached to a request by the `Authorization: Bearer` JWT.
+struct AuthUser {
+    id: String,
+    role: String,
+}
+
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let TypedHeader(Authorization(bearer)) = parts
+            .extract::<TypedHeader<Authorization<Bearer>>>()
+            .await
+            .map_err(|_| unauthorized("Missing bearer token"))?;
+
+        let claims = decode_token(&state.jwt_secret, bearer.token())
+            .map_err(|_| unauthorized("Invalid or expired token"))?;
+
+        Ok(AuthUser {
+            id: claims.sub,
+            role: claims.role,
+        })
+    }
+}
+
+/// Same as `AuthUser` but rejects non-admin roles, for gating admin-only routes.
+struct AdminUser(AuthUser);
+
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        if user.role != "admin" {
+            return Err(unauthorized("Admin required"));
+        }
+        Ok(AdminUser(user))
+    }
+}
+
 fn idx_token(parts: &[&str], token: &str) -> Option<usize> {
     parts.iter().position(|p| *p == token || p.starts_with(token))
 }
@@ -192,10 +425,10 @@ fn get_gpu_temp() -> f64 {
 
 fn get_cpu_usage() -> f64 {
     let mut sys = System::new_all();
-    sys.refresh_cpu();
+    sys.refresh_cpu_all();
     std::thread::sleep(Duration::from_millis(200));
-    sys.refresh_cpu();
-    sys.global_cpu_info().cpu_usage() as f64
+    sys.refresh_cpu_all();
+    sys.global_cpu_usage() as f64
 }
 
 fn iso_timestamp() -> String {
@@ -232,7 +465,7 @@ async fn telemetry_reader_loop(state: AppState) {
                 })
                 .unwrap_or(1);
 
-            if file_age > STALE_TIMEOUT_SECS {
+            if file_age > state.stale_timeout_secs {
                 let mut telemetry = state.telemetry.write().await;
                 telemetry.status = "stale".into();
                 telemetry.timestamp = Some(iso_timestamp());
@@ -290,10 +523,125 @@ async fn telemetry_reader_loop(state: AppState) {
             telemetry.timestamp = Some(iso_timestamp());
         }
 
-        tokio::time::sleep(Duration::from_secs(1)).await;
+        let snapshot = state.telemetry.read().await.clone();
+        let _ = state.telemetry_tx.send(snapshot.clone());
+
+        {
+            let mut history = state.history.write().await;
+            history.push_back(snapshot.clone());
+            while history.len() > HISTORY_RING_CAPACITY {
+                history.pop_front();
+            }
+        }
+        if let Ok(conn) = state.history_db.lock() {
+            insert_history_row(&conn, &snapshot);
+        }
+
+        tokio::time::sleep(Duration::from_secs(state.poll_interval_secs)).await;
     }
 }
 
+/// Opens (creating if needed) the SQLite database backing the telemetry history,
+/// so a rolling window of samples survives a server restart.
+fn init_history_db(path: &PathBuf) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS telemetry_history (
+            ts             TEXT PRIMARY KEY,
+            ms5611_temp    REAL,
+            ms5611_pressure REAL,
+            ms5611_altitude REAL,
+            mpu_gx REAL, mpu_gy REAL, mpu_gz REAL,
+            mpu_ax REAL, mpu_ay REAL, mpu_az REAL,
+            tmp REAL,
+            cpu REAL,
+            gpu_temp REAL
+        );",
+    )?;
+    Ok(conn)
+}
+
+fn insert_history_row(conn: &Connection, t: &Telemetry) {
+    let Some(ts) = t.timestamp.as_deref() else {
+        return;
+    };
+    let _ = conn.execute(
+        "INSERT OR REPLACE INTO telemetry_history VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13)",
+        params![
+            ts,
+            t.ms5611.temp,
+            t.ms5611.pressure,
+            t.ms5611.altitude,
+            t.mpu6050.gx,
+            t.mpu6050.gy,
+            t.mpu6050.gz,
+            t.mpu6050.ax,
+            t.mpu6050.ay,
+            t.mpu6050.az,
+            t.tmp,
+            t.system.cpu,
+            t.system.gpu_temp,
+        ],
+    );
+    prune_history(conn, HISTORY_RING_CAPACITY);
+}
+
+/// Keeps `history.sqlite` bounded to the same window as the in-memory ring buffer;
+/// without this, rows accumulate forever since every poll tick only ever inserts.
+fn prune_history(conn: &Connection, keep: usize) {
+    let _ = conn.execute(
+        "DELETE FROM telemetry_history WHERE ts NOT IN (
+            SELECT ts FROM telemetry_history ORDER BY ts DESC LIMIT ?1
+        )",
+        params![keep as i64],
+    );
+}
+
+/// Seeds the in-memory ring buffer from SQLite on startup, newest `limit` rows,
+/// restored to chronological order.
+fn load_history_rows(conn: &Connection, limit: usize) -> VecDeque<Telemetry> {
+    let mut stmt = match conn.prepare(
+        "SELECT ts, ms5611_temp, ms5611_pressure, ms5611_altitude,
+                mpu_gx, mpu_gy, mpu_gz, mpu_ax, mpu_ay, mpu_az,
+                tmp, cpu, gpu_temp
+         FROM telemetry_history ORDER BY ts DESC LIMIT ?1",
+    ) {
+        Ok(s) => s,
+        Err(_) => return VecDeque::new(),
+    };
+
+    let rows = stmt.query_map(params![limit as i64], |row| {
+        Ok(Telemetry {
+            status: "recorded".into(),
+            timestamp: Some(row.get(0)?),
+            ms5611: Ms5611 {
+                temp: row.get(1)?,
+                pressure: row.get(2)?,
+                altitude: row.get(3)?,
+            },
+            mpu6050: Mpu6050 {
+                gx: row.get(4)?,
+                gy: row.get(5)?,
+                gz: row.get(6)?,
+                ax: row.get(7)?,
+                ay: row.get(8)?,
+                az: row.get(9)?,
+            },
+            tmp: row.get(10)?,
+            system: SystemInfo {
+                cpu: row.get(11)?,
+                gpu_temp: row.get(12)?,
+            },
+        })
+    });
+
+    let newest_first: Vec<Telemetry> = match rows {
+        Ok(mapped) => mapped.filter_map(Result::ok).collect(),
+        Err(_) => return VecDeque::new(),
+    };
+    newest_first.into_iter().rev().collect()
+}
+
 async fn load_users(path: &PathBuf) -> Vec<User> {
     if let Ok(content) = fs::read_to_string(path).await {
         if let Ok(users) = serde_json::from_str::<Vec<User>>(&content) {
@@ -309,15 +657,178 @@ async fn save_users(path: &PathBuf, users: &[User]) {
     let _ = fs::write(&path, serde_json::to_string_pretty(users).unwrap_or_default()).await;
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/telemetry",
+    tag = "telemetry",
+    responses((status = 200, description = "Latest telemetry snapshot", body = Telemetry))
+)]
 async fn get_telemetry(State(state): State<AppState>) -> Json<Telemetry> {
     let telemetry = state.telemetry.read().await.clone();
     Json(telemetry)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/telemetry/stream",
+    tag = "telemetry",
+    responses(
+        (status = 200, description = "text/event-stream of Telemetry snapshots; emits a `status` event on stale/no_file transitions", body = Telemetry)
+    )
+)]
+async fn get_telemetry_stream(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let rx = state.telemetry_tx.subscribe();
+    let mut prev_status: Option<String> = None;
+    let stream = BroadcastStream::new(rx).filter_map(move |msg| {
+        let telemetry = msg.ok()?;
+        let event = Event::default().json_data(&telemetry).ok()?;
+        let is_down = matches!(telemetry.status.as_str(), "stale" | "no_file");
+        let transitioned = is_down && prev_status.as_deref() != Some(telemetry.status.as_str());
+        let event = if transitioned { event.event("status") } else { event };
+        prev_status = Some(telemetry.status.clone());
+        Some(Ok(event))
+    });
+
+    Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(SSE_KEEPALIVE_SECS))
+            .text("keep-alive"),
+    )
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    from: Option<String>,
+    to: Option<String>,
+    fields: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Reads off a dotted field path (e.g. `ms5611.altitude`, `mpu6050.az`, `system.cpu`) from a snapshot.
+fn telemetry_field_value(t: &Telemetry, path: &str) -> Option<f64> {
+    match path {
+        "tmp" => Some(t.tmp),
+        "ms5611.temp" => Some(t.ms5611.temp),
+        "ms5611.pressure" => Some(t.ms5611.pressure),
+        "ms5611.altitude" => Some(t.ms5611.altitude),
+        "mpu6050.gx" => Some(t.mpu6050.gx),
+        "mpu6050.gy" => Some(t.mpu6050.gy),
+        "mpu6050.gz" => Some(t.mpu6050.gz),
+        "mpu6050.ax" => Some(t.mpu6050.ax),
+        "mpu6050.ay" => Some(t.mpu6050.ay),
+        "mpu6050.az" => Some(t.mpu6050.az),
+        "system.cpu" => Some(t.system.cpu),
+        "system.gpu_temp" => Some(t.system.gpu_temp),
+        _ => None,
+    }
+}
+
+fn telemetry_point_json(t: &Telemetry, fields: &[String]) -> serde_json::Value {
+    if fields.is_empty() {
+        return serde_json::json!({
+            "ts": t.timestamp,
+            "ms5611": t.ms5611,
+            "mpu6050": t.mpu6050,
+            "tmp": t.tmp,
+            "system": t.system,
+        });
+    }
+
+    let mut values = serde_json::Map::new();
+    for field in fields {
+        if let Some(v) = telemetry_field_value(t, field) {
+            values.insert(field.clone(), serde_json::json!(v));
+        }
+    }
+    serde_json::json!({ "ts": t.timestamp, "values": values })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/telemetry/history",
+    tag = "telemetry",
+    params(
+        ("from" = Option<String>, Query, description = "RFC3339 start of range (inclusive)"),
+        ("to" = Option<String>, Query, description = "RFC3339 end of range (inclusive)"),
+        ("fields" = Option<String>, Query, description = "Comma-separated dotted field paths, e.g. ms5611.altitude,mpu6050.az"),
+        ("limit" = Option<usize>, Query, description = "Max points returned; larger windows are downsampled to this count"),
+    ),
+    responses((status = 200, description = "Downsampled time series", body = serde_json::Value))
+)]
+async fn get_telemetry_history(
+    State(state): State<AppState>,
+    Query(q): Query<HistoryQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let from = q
+        .from
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok());
+    let to = q
+        .to
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok());
+    let fields: Vec<String> = q
+        .fields
+        .as_deref()
+        .map(|s| {
+            s.split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let limit = q.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).max(1);
+
+    let history = state.history.read().await;
+    let filtered: Vec<&Telemetry> = history
+        .iter()
+        .filter(|t| {
+            let Some(ts) = t
+                .timestamp
+                .as_deref()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            else {
+                return false;
+            };
+            from.is_none_or(|f| ts >= f) && to.is_none_or(|t| ts <= t)
+        })
+        .collect();
+
+    // Downsample by taking every Nth point so large windows stay cheap to send.
+    // Ceiling division so the result never exceeds `limit`; plain integer division
+    // under-downsamples (and can return the whole window) for any filtered.len()
+    // between limit and 2*limit.
+    let step = filtered.len().div_ceil(limit).max(1);
+    let points: Vec<serde_json::Value> = filtered
+        .iter()
+        .step_by(step)
+        .map(|t| telemetry_point_json(t, &fields))
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "ok": true, "points": points })),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "telemetry",
+    responses((status = 200, description = "Liveness check", body = serde_json::Value))
+)]
 async fn get_health() -> Json<serde_json::Value> {
     Json(serde_json::json!({ "ok": true }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/users",
+    tag = "auth",
+    responses((status = 200, description = "Public user list (id, role, created — never passwords)", body = [serde_json::Value]))
+)]
 async fn get_users(State(state): State<AppState>) -> Json<Vec<serde_json::Value>> {
     let users = state.users.read().await;
     let public: Vec<serde_json::Value> = users
@@ -333,78 +844,194 @@ async fn get_users(State(state): State<AppState>) -> Json<Vec<serde_json::Value>
     Json(public)
 }
 
+#[derive(Serialize, utoipa::ToSchema)]
+struct ConnectedClient {
+    remote_addr: String,
+    state: String,
+    pid: Option<u32>,
+    process_name: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/clients",
+    tag = "telemetry",
+    responses((status = 200, description = "Established TCP connections to the telemetry port", body = [ConnectedClient])),
+    security(("bearer_auth" = []))
+)]
+async fn get_clients(State(state): State<AppState>, _admin: AdminUser) -> Json<Vec<ConnectedClient>> {
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let proto_flags = ProtocolFlags::TCP;
+    let sockets = get_sockets_info(af_flags, proto_flags).unwrap_or_default();
+
+    let mut sys = System::new_all();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+    let clients: Vec<ConnectedClient> = sockets
+        .into_iter()
+        .filter_map(|socket| {
+            let ProtocolSocketInfo::Tcp(tcp) = socket.protocol_socket_info else {
+                return None;
+            };
+            if tcp.local_port != state.port
+                || !format!("{:?}", tcp.state).eq_ignore_ascii_case("established")
+            {
+                return None;
+            }
+
+            let pid = socket.associated_pids.first().copied();
+            let process_name = pid.and_then(|p| {
+                sys.process(Pid::from_u32(p))
+                    .map(|proc| proc.name().to_string_lossy().into_owned())
+            });
+
+            Some(ConnectedClient {
+                remote_addr: format!("{}:{}", tcp.remote_addr, tcp.remote_port),
+                state: format!("{:?}", tcp.state),
+                pid,
+                process_name,
+            })
+        })
+        .collect();
+
+    Json(clients)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth",
+    tag = "auth",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "Credentials valid; returns a JWT bearer token", body = serde_json::Value),
+        (status = 401, description = "Invalid credentials", body = serde_json::Value),
+        (status = 500, description = "Token issuance failed", body = serde_json::Value),
+    )
+)]
 async fn post_auth(
     State(state): State<AppState>,
     Json(body): Json<serde_json::Value>,
-) -> (axum::http::StatusCode, Json<serde_json::Value>) {
+) -> (StatusCode, Json<serde_json::Value>) {
     let id = body.get("id").and_then(|v| v.as_str()).unwrap_or("").trim();
     let pw = body.get("pw").and_then(|v| v.as_str()).unwrap_or("");
 
-    let users = state.users.read().await;
-    let match_user = users.iter().find(|u| {
-        u.id.eq_ignore_ascii_case(id) && u.pw.as_deref().unwrap_or("") == pw
-    });
+    // Only hold a read lock while copying out the stored hash, so concurrent
+    // logins (and unrelated GET /api/users calls) aren't blocked behind the
+    // CPU-expensive Argon2 verify below.
+    let found = {
+        let users = state.users.read().await;
+        users.iter().find(|u| u.id.eq_ignore_ascii_case(id)).map(|u| u.pw.clone())
+    };
+    let known_user = found.is_some();
+
+    // Always run the Argon2 verify, against the account's real hash if it has
+    // one or a fixed dummy hash otherwise, so the expensive part of this
+    // function costs the same whether the account doesn't exist, is still on
+    // legacy cleartext, or has already migrated to Argon2 -- none of those
+    // should be distinguishable from response timing.
+    let phc_for_timing = found
+        .as_deref()
+        .filter(|h| is_phc_hash(h))
+        .unwrap_or_else(|| dummy_password_hash());
+    let argon2_matches = verify_password(pw, phc_for_timing);
+
+    let password_matches = match found.as_deref() {
+        Some(stored) if is_phc_hash(stored) => argon2_matches,
+        // Legacy cleartext entry from before Argon2 hashing was added.
+        Some(stored) => stored == pw,
+        None => false,
+    };
+    let authenticated = known_user && password_matches;
 
-    if let Some(u) = match_user {
+    if !authenticated {
         return (
-            axum::http::StatusCode::OK,
-            Json(serde_json::json!({
-                "ok": true,
-                "user": {
-                    "id": u.id,
-                    "role": u.role,
-                    "created": u.created
-                }
-            })),
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({ "ok": false, "error": "Invalid credentials" })),
         );
     }
 
+    // Re-acquire the lock only to persist the legacy -> Argon2 migration, and only
+    // for as long as it takes to mutate the in-memory table and clone the result.
+    let u = {
+        let mut users = state.users.write().await;
+        let Some(idx) = users.iter().position(|u| u.id.eq_ignore_ascii_case(id)) else {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({ "ok": false, "error": "Invalid credentials" })),
+            );
+        };
+        if !is_phc_hash(&users[idx].pw) {
+            users[idx].pw = hash_password(pw);
+            save_users(&state.users_file, &users).await;
+        }
+        users[idx].clone()
+    };
+
+    let token = match issue_token(&state.jwt_secret, &u.id, &u.role) {
+        Ok(t) => t,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "ok": false, "error": "Failed to issue session token" })),
+            )
+        }
+    };
+
     (
-        axum::http::StatusCode::UNAUTHORIZED,
-        Json(serde_json::json!({ "ok": false, "error": "Invalid credentials" })),
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "ok": true,
+            "token": token,
+            "user": {
+                "id": u.id,
+                "role": u.role,
+                "created": u.created
+            }
+        })),
     )
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/users",
+    tag = "auth",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "User created", body = serde_json::Value),
+        (status = 400, description = "Validation error (bad username/password, or duplicate)", body = serde_json::Value),
+        (status = 401, description = "Admin token missing/invalid", body = serde_json::Value),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn post_users(
     State(state): State<AppState>,
+    _admin: AdminUser,
     Json(body): Json<serde_json::Value>,
-) -> (axum::http::StatusCode, Json<serde_json::Value>) {
-    let admin_id = body.get("adminId").and_then(|v| v.as_str()).unwrap_or("").trim();
-    let admin_pw = body.get("adminPw").and_then(|v| v.as_str()).unwrap_or("");
+) -> (StatusCode, Json<serde_json::Value>) {
     let uid = body.get("id").and_then(|v| v.as_str()).unwrap_or("").trim();
     let pw = body.get("pw").and_then(|v| v.as_str()).unwrap_or("");
     let role = body.get("role").and_then(|v| v.as_str()).unwrap_or("guest").trim();
     let role = if role.is_empty() { "guest" } else { role };
 
     let mut users = state.users.read().await.clone();
-    let admin = users.iter().find(|u| {
-        u.id.eq_ignore_ascii_case(admin_id) && u.pw.as_deref().unwrap_or("") == admin_pw
-    });
-
-    if admin.is_none() || admin.unwrap().role != "admin" {
-        return (
-            axum::http::StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({ "ok": false, "error": "Admin required" })),
-        );
-    }
 
     if uid.len() < 3 {
         return (
-            axum::http::StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
             Json(serde_json::json!({ "ok": false, "error": "Username required (≥3 chars)" })),
         );
     }
 
     if pw.len() < 6 {
         return (
-            axum::http::StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
             Json(serde_json::json!({ "ok": false, "error": "Password must be ≥6 characters" })),
         );
     }
 
     if users.iter().any(|u| u.id.eq_ignore_ascii_case(uid)) {
         return (
-            axum::http::StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
             Json(serde_json::json!({ "ok": false, "error": "Username already exists" })),
         );
     }
@@ -412,118 +1039,200 @@ async fn post_users(
     let created = chrono::Utc::now().format("%Y-%m-%d").to_string();
     users.push(User {
         id: uid.to_string(),
-        pw: Some(pw.to_string()),
+        pw: hash_password(pw),
         role: role.to_string(),
         created,
     });
 
     let mut users_guard = state.users.write().await;
     *users_guard = users;
-    save_users(&state.users_file, &*users_guard).await;
+    save_users(&state.users_file, &users_guard).await;
 
-    (
-        axum::http::StatusCode::OK,
-        Json(serde_json::json!({ "ok": true })),
-    )
+    (StatusCode::OK, Json(serde_json::json!({ "ok": true })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/users/delete",
+    tag = "auth",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "User deleted", body = serde_json::Value),
+        (status = 400, description = "Protected user or self-delete attempted", body = serde_json::Value),
+        (status = 401, description = "Admin token missing/invalid", body = serde_json::Value),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn post_users_delete(
     State(state): State<AppState>,
+    admin: AdminUser,
     Json(body): Json<serde_json::Value>,
-) -> (axum::http::StatusCode, Json<serde_json::Value>) {
-    let admin_id = body.get("adminId").and_then(|v| v.as_str()).unwrap_or("").trim();
-    let admin_pw = body.get("adminPw").and_then(|v| v.as_str()).unwrap_or("");
+) -> (StatusCode, Json<serde_json::Value>) {
     let target_id = body.get("id").and_then(|v| v.as_str()).unwrap_or("").trim();
 
-    let mut users = state.users.read().await.clone();
-    let admin = users.iter().find(|u| {
-        u.id.eq_ignore_ascii_case(admin_id) && u.pw.as_deref().unwrap_or("") == admin_pw
-    });
-
-    if admin.is_none() || admin.unwrap().role != "admin" {
-        return (
-            axum::http::StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({ "ok": false, "error": "Admin required" })),
-        );
-    }
-
     if target_id.eq_ignore_ascii_case("flyboysam") {
         return (
-            axum::http::StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
             Json(serde_json::json!({ "ok": false, "error": "Protected user" })),
         );
     }
 
-    if target_id.eq_ignore_ascii_case(admin_id) {
+    if target_id.eq_ignore_ascii_case(&admin.0.id) {
         return (
-            axum::http::StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
             Json(serde_json::json!({ "ok": false, "error": "Cannot remove your own account" })),
         );
     }
 
+    let mut users = state.users.read().await.clone();
     users.retain(|u| !u.id.eq_ignore_ascii_case(target_id));
 
     let mut users_guard = state.users.write().await;
     *users_guard = users;
-    save_users(&state.users_file, &*users_guard).await;
+    save_users(&state.users_file, &users_guard).await;
 
-    (
-        axum::http::StatusCode::OK,
-        Json(serde_json::json!({ "ok": true })),
-    )
+    (StatusCode::OK, Json(serde_json::json!({ "ok": true })))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_telemetry,
+        get_telemetry_stream,
+        get_telemetry_history,
+        get_health,
+        get_users,
+        get_clients,
+        post_users,
+        post_users_delete,
+        post_auth,
+    ),
+    components(schemas(Ms5611, Mpu6050, SystemInfo, Telemetry, User, ConnectedClient)),
+    tags(
+        (name = "telemetry", description = "Live and historical telemetry from the CubeSat simulator"),
+        (name = "auth", description = "Authentication and user management"),
+    ),
+    modifiers(&SecurityAddon)
+)]
+struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
     let script_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let config = load_config(&script_dir.join("config.toml"));
+
     let users_file = script_dir.join("users.json");
-    let telem_file = std::env::var("TELEM_FILE")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| script_dir.join("telem.txt"));
+    let telem_file = {
+        let configured = PathBuf::from(&config.telemetry.file);
+        if configured.is_absolute() {
+            configured
+        } else {
+            script_dir.join(configured)
+        }
+    };
 
     let users = load_users(&users_file).await;
     if !users_file.exists() {
         save_users(&users_file, &users).await;
     }
 
+    let (telemetry_tx, _) = broadcast::channel(TELEMETRY_BROADCAST_CAPACITY);
+
+    let history_db_path = script_dir.join("history.sqlite");
+    let history_conn =
+        init_history_db(&history_db_path).expect("failed to open telemetry history database");
+    let seeded_history = load_history_rows(&history_conn, HISTORY_RING_CAPACITY);
+
     let state = AppState {
         telemetry: Arc::new(RwLock::new(Telemetry::default())),
+        telemetry_tx,
+        history: Arc::new(RwLock::new(seeded_history)),
+        history_db: Arc::new(Mutex::new(history_conn)),
         users: Arc::new(RwLock::new(users)),
         users_file: users_file.clone(),
         telem_file: telem_file.clone(),
+        poll_interval_secs: config.telemetry.poll_interval_secs,
+        stale_timeout_secs: config.telemetry.stale_timeout_secs,
+        jwt_secret: config.security.jwt_secret.clone(),
+        port: config.server.port,
     };
 
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers([header::CONTENT_TYPE]);
+    let cors = if config.security.cors_allowed_origins.is_empty() {
+        CorsLayer::new()
+            .allow_origin(Any)
+            .allow_methods(Any)
+            .allow_headers([header::CONTENT_TYPE])
+    } else {
+        let origins: Vec<_> = config
+            .security
+            .cors_allowed_origins
+            .iter()
+            .filter_map(|o| o.parse().ok())
+            .collect();
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods(Any)
+            .allow_headers([header::CONTENT_TYPE])
+    };
 
-    let dashboard_root = script_dir
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| script_dir.clone());
+    let dashboard_root = config
+        .server
+        .dashboard_root
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            script_dir
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_else(|| script_dir.clone())
+        });
+
+    // Kept on its own router, outside CompressionLayer: gzip buffers output before
+    // flushing a chunk, which would delay delivery of SSE frames to any client
+    // sending Accept-Encoding: gzip and defeat the point of pushing telemetry live.
+    let stream_routes = Router::new()
+        .route("/telemetry/stream", get(get_telemetry_stream))
+        .with_state(state.clone());
 
     let api_routes = Router::new()
         .route("/telemetry", get(get_telemetry))
+        .route("/telemetry/history", get(get_telemetry_history))
         .route("/health", get(get_health))
         .route("/users", get(get_users).post(post_users))
         .route("/users/delete", post(post_users_delete))
+        .route("/clients", get(get_clients))
         .route("/auth", post(post_auth))
-        .with_state(state.clone());
+        .with_state(state.clone())
+        .layer(CompressionLayer::new());
 
     let app = Router::new()
-        .nest("/api", api_routes)
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
+        .nest("/api", api_routes.merge(stream_routes))
         .nest_service("/", ServeDir::new(&dashboard_root))
         .layer(cors);
 
     tokio::spawn(telemetry_reader_loop(state));
 
-    let port = std::env::var("PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(DEFAULT_PORT);
+    let port = config.server.port;
+    let ip: std::net::IpAddr = config
+        .server
+        .bind_addr
+        .parse()
+        .unwrap_or_else(|_| std::net::IpAddr::V4(std::net::Ipv4Addr::new(0, 0, 0, 0)));
+    let addr = std::net::SocketAddr::new(ip, port);
 
-    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
     println!(
         r#"
 ╔══════════════════════════════════════════════════════╗
@@ -542,9 +1251,153 @@ async fn main() {
         Err(e) => {
             eprintln!("Failed to bind to port {}: {}", port, e);
             eprintln!("Port may be in use. Try: 1) Close other instances of this app");
-            eprintln!("2) Set PORT=5051 (or another port) and run again");
+            eprintln!("2) Set PORT=5051 (or another port) and run again, or edit config.toml");
             std::process::exit(1);
         }
     };
     axum::serve(listener, app).await.expect("serve");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn test_state(users: Vec<User>) -> AppState {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let users_file = std::env::temp_dir().join(format!("srg_test_users_{}_{}.json", std::process::id(), n));
+        let history_conn = Connection::open_in_memory().expect("open in-memory sqlite");
+        let (telemetry_tx, _) = broadcast::channel(TELEMETRY_BROADCAST_CAPACITY);
+        AppState {
+            telemetry: Arc::new(RwLock::new(Telemetry::default())),
+            telemetry_tx,
+            history: Arc::new(RwLock::new(VecDeque::new())),
+            history_db: Arc::new(Mutex::new(history_conn)),
+            users: Arc::new(RwLock::new(users)),
+            users_file,
+            telem_file: PathBuf::from("telem.txt"),
+            poll_interval_secs: 1,
+            stale_timeout_secs: STALE_TIMEOUT_SECS,
+            jwt_secret: "test-secret".into(),
+            port: 0,
+        }
+    }
+
+    #[test]
+    fn hash_password_round_trips_with_verify_password() {
+        let hash = hash_password("correct horse battery staple");
+        assert!(is_phc_hash(&hash));
+        assert!(verify_password("correct horse battery staple", &hash));
+        assert!(!verify_password("wrong password", &hash));
+    }
+
+    #[test]
+    fn verify_password_rejects_garbage_hash() {
+        assert!(!verify_password("anything", "not-a-phc-string"));
+    }
+
+    #[tokio::test]
+    async fn post_auth_accepts_legacy_cleartext_and_migrates_to_argon2() {
+        let state = test_state(vec![User {
+            id: "legacy".into(),
+            pw: "plaintext-pw".into(),
+            role: "guest".into(),
+            created: "2026-01-01".into(),
+        }]);
+
+        let (status, body) = post_auth(
+            State(state.clone()),
+            Json(serde_json::json!({ "id": "legacy", "pw": "plaintext-pw" })),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.0["ok"], serde_json::json!(true));
+
+        let users = state.users.read().await;
+        let migrated = users.iter().find(|u| u.id == "legacy").unwrap();
+        assert!(is_phc_hash(&migrated.pw));
+        assert!(verify_password("plaintext-pw", &migrated.pw));
+        let _ = std::fs::remove_file(&state.users_file);
+    }
+
+    #[tokio::test]
+    async fn post_auth_rejects_wrong_password() {
+        let state = test_state(vec![User {
+            id: "u".into(),
+            pw: hash_password("right"),
+            role: "guest".into(),
+            created: "2026-01-01".into(),
+        }]);
+
+        let (status, body) = post_auth(
+            State(state.clone()),
+            Json(serde_json::json!({ "id": "u", "pw": "wrong" })),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body.0["ok"], serde_json::json!(false));
+        let _ = std::fs::remove_file(&state.users_file);
+    }
+
+    #[test]
+    fn telemetry_field_value_reads_dotted_paths() {
+        let t = Telemetry {
+            ms5611: Ms5611 { altitude: 123.5, ..Default::default() },
+            system: SystemInfo { cpu: 42.0, ..Default::default() },
+            ..Default::default()
+        };
+        assert_eq!(telemetry_field_value(&t, "ms5611.altitude"), Some(123.5));
+        assert_eq!(telemetry_field_value(&t, "system.cpu"), Some(42.0));
+        assert_eq!(telemetry_field_value(&t, "no.such.field"), None);
+    }
+
+    #[test]
+    fn telemetry_point_json_includes_only_requested_fields() {
+        let t = Telemetry {
+            tmp: 10.0,
+            ms5611: Ms5611 { pressure: 1013.0, ..Default::default() },
+            ..Default::default()
+        };
+
+        let point = telemetry_point_json(&t, &["ms5611.pressure".to_string()]);
+        assert_eq!(point["values"]["ms5611.pressure"], serde_json::json!(1013.0));
+        assert!(point["values"].get("tmp").is_none());
+
+        let full_point = telemetry_point_json(&t, &[]);
+        assert_eq!(full_point["tmp"], serde_json::json!(10.0));
+    }
+
+    fn history_of_len(n: usize) -> VecDeque<Telemetry> {
+        (0..n)
+            .map(|i| Telemetry {
+                timestamp: Some(
+                    chrono::DateTime::from_timestamp(1_700_000_000 + i as i64, 0)
+                        .unwrap()
+                        .to_rfc3339(),
+                ),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn get_telemetry_history_never_exceeds_limit() {
+        for len in [500, 799, 999, 10_000] {
+            let state = test_state(vec![]);
+            *state.history.write().await = history_of_len(len);
+
+            let (status, body) = get_telemetry_history(
+                State(state),
+                Query(HistoryQuery { from: None, to: None, fields: None, limit: Some(500) }),
+            )
+            .await;
+
+            assert_eq!(status, StatusCode::OK);
+            let points = body.0["points"].as_array().unwrap();
+            assert!(points.len() <= 500, "len={} returned={}", len, points.len());
+        }
+    }
+}